@@ -3,17 +3,242 @@
 
 mod stream;
 
-use tauri::Manager;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use arboard::Clipboard;
+use enigo::{Enigo, Key, KeyboardControllable};
+use tauri::api::dialog::blocking::ask;
+use tauri::{
+    CustomMenuItem, GlobalShortcutManager, Manager, SystemTray, SystemTrayEvent, SystemTrayMenu,
+    SystemTrayMenuItem, WindowEvent,
+};
+use tauri_plugin_window_state::{StateFlags, WindowExt};
 
 #[tauri::command]
 fn open_devtools(window: tauri::Window) {
     window.open_devtools();
 }
 
+// Stashes `text` into the system clipboard, hands focus back to whatever
+// window NextChat stole it from, then simulates the platform paste chord.
+// Used by the quick-ask hotkey to inject a model reply into the active app.
+//
+// Which window ends up focused after the Alt/Cmd+Tab below is a guess, not
+// a guarantee — with more than two windows open this can land on something
+// unrelated to where the user was working. Since `text` can contain
+// arbitrary multi-line shell snippets, blindly pasting it as OS-level
+// keystrokes risks running commands in whatever window it lands on. Always
+// make the user confirm the exact text before a single key is sent.
+#[tauri::command]
+fn inject_text(window: tauri::Window, text: String) -> Result<(), String> {
+    let confirmed = ask(
+        Some(&window),
+        "Paste NextChat reply?",
+        format!("Type the following into the previously focused window?\n\n{text}"),
+    );
+    if !confirmed {
+        return Ok(());
+    }
+
+    let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
+    let previous_clipboard = clipboard.get_text().ok();
+    clipboard.set_text(text).map_err(|e| e.to_string())?;
+
+    let mut enigo = Enigo::new();
+
+    // macOS's app switcher is Cmd+Tab, not Option+Tab (which has no default
+    // binding) — gate this the same way the paste chord below already is, or
+    // focus never actually leaves NextChat's own window.
+    #[cfg(target_os = "macos")]
+    let switch_modifier = Key::Meta;
+    #[cfg(not(target_os = "macos"))]
+    let switch_modifier = Key::Alt;
+
+    enigo.key_down(switch_modifier);
+    enigo.key_click(Key::Tab);
+    enigo.key_up(switch_modifier);
+    thread::sleep(Duration::from_millis(150));
+
+    #[cfg(target_os = "macos")]
+    let paste_modifier = Key::Meta;
+    #[cfg(not(target_os = "macos"))]
+    let paste_modifier = Key::Control;
+
+    enigo.key_down(paste_modifier);
+    enigo.key_click(Key::Layout('v'));
+    enigo.key_up(paste_modifier);
+
+    // Give the target app time to read the injected value before we put the
+    // user's own clipboard contents back.
+    thread::sleep(Duration::from_millis(200));
+    if let Some(previous) = previous_clipboard {
+        clipboard.set_text(previous).ok();
+    }
+
+    Ok(())
+}
+
+fn emit_quick_ask(app: &tauri::AppHandle) {
+    app.emit_all("quick-ask://trigger", ()).ok();
+}
+
+// Tracks whichever shortcut string is currently bound to `emit_quick_ask`, so
+// that replacing it always unregisters the right binding — not just the one
+// the caller happens to pass in.
+struct QuickAskShortcutState(Mutex<Option<String>>);
+
+#[tauri::command]
+fn register_quick_ask_shortcut(
+    app: tauri::AppHandle,
+    state: tauri::State<QuickAskShortcutState>,
+    shortcut: String,
+) -> Result<(), String> {
+    let mut manager = app.global_shortcut_manager();
+    let mut active = state.0.lock().unwrap();
+    if let Some(previous) = active.as_ref() {
+        if manager.is_registered(previous).unwrap_or(false) {
+            manager.unregister(previous).map_err(|e| e.to_string())?;
+        }
+        // The previous binding is gone from the OS at this point either way
+        // (it wasn't registered, or we just unregistered it) — clear it now
+        // so in-memory state can't keep claiming a shortcut that isn't
+        // actually bound anymore if the registration below fails.
+        *active = None;
+    }
+    manager
+        .register(&shortcut, move || emit_quick_ask(&app))
+        .map_err(|e| e.to_string())?;
+    *active = Some(shortcut);
+    Ok(())
+}
+
+#[tauri::command]
+fn unregister_quick_ask_shortcut(
+    app: tauri::AppHandle,
+    state: tauri::State<QuickAskShortcutState>,
+) -> Result<(), String> {
+    let mut active = state.0.lock().unwrap();
+    if let Some(shortcut) = active.take() {
+        app.global_shortcut_manager()
+            .unregister(&shortcut)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+const DEFAULT_QUICK_ASK_SHORTCUT: &str = "CommandOrControl+Shift+Space";
+
+#[derive(Clone, Copy, PartialEq)]
+enum CloseBehavior {
+    MinimizeToTray,
+    Prompt,
+    Exit,
+}
+
+impl CloseBehavior {
+    fn as_str(&self) -> &'static str {
+        match self {
+            CloseBehavior::MinimizeToTray => "minimize",
+            CloseBehavior::Prompt => "prompt",
+            CloseBehavior::Exit => "exit",
+        }
+    }
+}
+
+struct CloseBehaviorState(Mutex<CloseBehavior>);
+
+#[tauri::command]
+fn set_close_behavior(state: tauri::State<CloseBehaviorState>, behavior: String) -> Result<(), String> {
+    let parsed = match behavior.as_str() {
+        "minimize" => CloseBehavior::MinimizeToTray,
+        "prompt" => CloseBehavior::Prompt,
+        "exit" => CloseBehavior::Exit,
+        other => return Err(format!("unknown close behavior: {other}")),
+    };
+    *state.0.lock().unwrap() = parsed;
+    Ok(())
+}
+
+// Returns the current close behavior as the string `set_close_behavior`
+// accepts. `CloseBehaviorState` only lives for the process lifetime and is
+// reset to the default on every launch; a choice kept in the frontend's own
+// settings store is put back with `set_close_behavior`.
+#[tauri::command]
+fn get_close_behavior(state: tauri::State<CloseBehaviorState>) -> String {
+    state.0.lock().unwrap().as_str().to_string()
+}
+
+// Called by the frontend once the user confirms the "exit?" dialog raised
+// for the `Prompt` close behavior.
+#[tauri::command]
+fn confirm_exit(app: tauri::AppHandle) {
+    app.exit(0);
+}
+
+fn build_tray_menu() -> SystemTrayMenu {
+    SystemTrayMenu::new()
+        .add_item(CustomMenuItem::new("toggle_visibility", "Show/Hide Window"))
+        .add_item(CustomMenuItem::new("new_chat", "New Chat"))
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new("quit", "Quit"))
+}
+
+fn show_main_window(app: &tauri::AppHandle) {
+    let window = app.get_window("main").unwrap();
+    window.restore_state(StateFlags::all()).ok();
+    if window.is_minimized().unwrap_or(false) {
+        window.unminimize().ok();
+    }
+    window.show().ok();
+    window.set_focus().ok();
+}
+
+fn toggle_main_window(app: &tauri::AppHandle) {
+    let window = app.get_window("main").unwrap();
+    // `is_visible()` alone can't tell "visible but minimized" from "visible
+    // and on screen" — only hide a window that's visible and not minimized,
+    // otherwise restore/focus it instead of burying it further. Focus and
+    // stacking order are not checked.
+    let is_showing = window.is_visible().unwrap_or(false) && !window.is_minimized().unwrap_or(false);
+    if is_showing {
+        window.hide().ok();
+    } else {
+        show_main_window(app);
+    }
+}
+
 fn main() {
   tauri::Builder::default()
-    .invoke_handler(tauri::generate_handler![stream::stream_fetch, open_devtools])
+    .invoke_handler(tauri::generate_handler![
+      stream::stream_fetch,
+      stream::stream_cancel,
+      open_devtools,
+      inject_text,
+      register_quick_ask_shortcut,
+      unregister_quick_ask_shortcut,
+      set_close_behavior,
+      get_close_behavior,
+      confirm_exit
+    ])
+    .manage(CloseBehaviorState(Mutex::new(CloseBehavior::MinimizeToTray)))
+    .manage(QuickAskShortcutState(Mutex::new(None)))
+    .manage(stream::StreamCancelState::default())
     .plugin(tauri_plugin_window_state::Builder::default().build())
+    .system_tray(SystemTray::new().with_menu(build_tray_menu()))
+    .on_system_tray_event(|app, event| match event {
+      SystemTrayEvent::LeftClick { .. } => toggle_main_window(app),
+      SystemTrayEvent::MenuItemClick { id, .. } => match id.as_str() {
+        "toggle_visibility" => toggle_main_window(app),
+        "new_chat" => {
+          app.get_window("main").unwrap().emit("tray://new-chat", ()).ok();
+        }
+        "quit" => app.exit(0),
+        _ => {}
+      },
+      _ => {}
+    })
     .setup(|app| {
       // 在 debug 模式自動開啟開發者工具
       #[cfg(debug_assertions)]
@@ -21,8 +246,37 @@ fn main() {
         let window = app.get_window("main").unwrap();
         window.open_devtools();
       }
+
+      let app_handle = app.handle();
+      if app
+        .global_shortcut_manager()
+        .register(DEFAULT_QUICK_ASK_SHORTCUT, move || emit_quick_ask(&app_handle))
+        .is_ok()
+      {
+        *app.state::<QuickAskShortcutState>().0.lock().unwrap() =
+          Some(DEFAULT_QUICK_ASK_SHORTCUT.to_string());
+      }
+
       Ok(())
     })
+    .on_window_event(|event| {
+      if let WindowEvent::CloseRequested { api, .. } = event.event() {
+        let window = event.window();
+        let app = window.app_handle();
+        let behavior = *app.state::<CloseBehaviorState>().0.lock().unwrap();
+        match behavior {
+          CloseBehavior::Exit => {}
+          CloseBehavior::MinimizeToTray => {
+            api.prevent_close();
+            window.hide().ok();
+          }
+          CloseBehavior::Prompt => {
+            api.prevent_close();
+            window.emit("close://confirm", ()).ok();
+          }
+        }
+      }
+    })
     .run(tauri::generate_context!())
     .expect("error while running tauri application");
 }
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use futures_util::StreamExt;
+use tauri::Window;
+use tokio_util::sync::CancellationToken;
+
+// Tracks the cancellation token for every in-flight `stream_fetch` call, keyed
+// by the caller-supplied request id, so `stream_cancel` can reach across from
+// a different command invocation.
+#[derive(Default)]
+pub struct StreamCancelState(pub Mutex<HashMap<u32, CancellationToken>>);
+
+#[tauri::command]
+pub async fn stream_fetch(
+    request_id: u32,
+    url: String,
+    method: String,
+    headers: HashMap<String, String>,
+    body: String,
+    window: Window,
+    state: tauri::State<'_, StreamCancelState>,
+) -> Result<(), String> {
+    let token = CancellationToken::new();
+    {
+        let mut streams = state.0.lock().unwrap();
+        if streams.contains_key(&request_id) {
+            return Err(format!("request id {request_id} is already in use"));
+        }
+        streams.insert(request_id, token.clone());
+    }
+
+    let result = run_stream(&url, &method, &headers, &body, &window, request_id, &token).await;
+
+    // Only drop our own token. The duplicate-id check above keeps any other
+    // call from registering this id while ours is in the map, so this
+    // comparison is a safeguard rather than a case that occurs today.
+    let mut streams = state.0.lock().unwrap();
+    if let Some(current) = streams.get(&request_id) {
+        if current.eq(&token) {
+            streams.remove(&request_id);
+        }
+    }
+    drop(streams);
+
+    window
+        .emit(
+            &format!("endpoint://{request_id}"),
+            serde_json::json!({ "done": true, "cancelled": token.is_cancelled() }),
+        )
+        .ok();
+
+    result
+}
+
+async fn run_stream(
+    url: &str,
+    method: &str,
+    headers: &HashMap<String, String>,
+    body: &str,
+    window: &Window,
+    request_id: u32,
+    token: &CancellationToken,
+) -> Result<(), String> {
+    let client = reqwest::Client::new();
+    let mut builder = client.request(method.parse().map_err(|_| "invalid method".to_string())?, url);
+    for (key, value) in headers {
+        builder = builder.header(key, value);
+    }
+    if !body.is_empty() {
+        builder = builder.body(body.to_string());
+    }
+
+    let response = tokio::select! {
+        _ = token.cancelled() => return Ok(()),
+        result = builder.send() => result.map_err(|e| e.to_string())?,
+    };
+    let mut bytes_stream = response.bytes_stream();
+
+    loop {
+        let chunk = tokio::select! {
+            _ = token.cancelled() => break,
+            next = bytes_stream.next() => match next {
+                Some(chunk) => chunk.map_err(|e| e.to_string())?,
+                None => break,
+            },
+        };
+        window
+            .emit(
+                &format!("endpoint://{request_id}"),
+                serde_json::json!({ "chunk": chunk.to_vec() }),
+            )
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+// Aborts the stream for `request_id` mid-flight, including while it's
+// blocked waiting on the network: the `tokio::select!` in `run_stream` races
+// every await against `token.cancelled()` and drops the connection as soon
+// as it fires.
+#[tauri::command]
+pub fn stream_cancel(request_id: u32, state: tauri::State<StreamCancelState>) -> Result<(), String> {
+    match state.0.lock().unwrap().get(&request_id) {
+        Some(token) => {
+            token.cancel();
+            Ok(())
+        }
+        None => Err(format!("no active stream with id {request_id}")),
+    }
+}